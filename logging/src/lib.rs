@@ -1,11 +1,20 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
 
 use anyhow::{anyhow, Result};
+use flate2::{write::GzEncoder, Compression};
 
 use crate::kinode::process::logging::Request as LoggingRequest;
 use kinode_process_lib::logging::{error, info, init_logging, Level};
-use kinode_process_lib::vfs::{create_drive, open_dir, open_file, File};
-use kinode_process_lib::{await_message, call_init, Address, Message, PackageId};
+use kinode_process_lib::vfs::{
+    create_drive, create_file, open_dir, open_file, remove_file, File, FileType,
+};
+use kinode_process_lib::{await_message, call_init, Address, Message, PackageId, Response};
+
+/// default cap on a single `.log` file before it is rotated
+const DEFAULT_MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+/// default number of rotated `.log.N.gz` segments to retain per `Address`
+const DEFAULT_MAX_LOG_FILES: usize = 5;
 
 wit_bindgen::generate!({
     path: "target/wit",
@@ -19,6 +28,59 @@ wit_bindgen::generate!({
 enum Req {
     LoggingRequest(LoggingRequest),
     InternalRequest(InternalRequest),
+    QueryRequest(QueryRequest),
+    VersionRequest(VersionRequest),
+    MetricsRequest(MetricsRequest),
+}
+
+/// request a snapshot of `State::metrics`
+#[derive(Debug, serde::Deserialize, serde::Serialize, process_macros::SerdeJsonInto)]
+enum MetricsRequest {
+    GetMetrics,
+}
+
+/// lightweight observability counters over `handle_message`'s own filtering behavior
+#[derive(Debug, Default, serde::Serialize)]
+struct Metrics {
+    accepted_total: u64,
+    dropped_by_reason: HashMap<DropReason, u64>,
+    accepted_by_package: HashMap<String, u64>,
+    accepted_by_node: HashMap<String, u64>,
+}
+
+impl Metrics {
+    fn record_drop(&mut self, reason: DropReason) {
+        *self.dropped_by_reason.entry(reason).or_insert(0) += 1;
+    }
+
+    fn record_accepted(&mut self, source: &Address) {
+        self.accepted_total += 1;
+        *self
+            .accepted_by_package
+            .entry(source.package_id().to_string())
+            .or_insert(0) += 1;
+        *self
+            .accepted_by_node
+            .entry(source.node().to_string())
+            .or_insert(0) += 1;
+    }
+}
+
+/// oldest and newest `LoggingRequest::Log` wire versions this build understands
+const MIN_SUPPORTED_LOG_PROTOCOL_VERSION: u32 = 1;
+const MAX_SUPPORTED_LOG_PROTOCOL_VERSION: u32 = 1;
+
+/// sent by a remote node before its first `Log`, to negotiate the wire format in use
+#[derive(Debug, serde::Deserialize, serde::Serialize, process_macros::SerdeJsonInto)]
+enum VersionRequest {
+    Hello(u32),
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct VersionResponse {
+    min_supported: u32,
+    max_supported: u32,
+    accepted: bool,
 }
 
 type Packages = HashSet<PackageId>;
@@ -33,19 +95,61 @@ enum InternalRequest {
     UnwhitelistNode(String),
     BlacklistNode(String),
     UnblacklistNode(String),
+    SetMinLevel(String),
+    SetPackageMinLevel(PackageId, String),
+    SetMaxLogBytes(u64),
+    SetMaxLogFileCount(usize),
 }
 
-/// drive_path       : populated at process init()
-/// log_files        : added to over the run of the program to reduce number of VFS calls
-/// allowed_packages : packages to log for; empty -> all
-/// whitelist        : nodes to log for; empty -> all
-/// blacklist        : nodes to NOT log for; empty -> all
+/// parse a level string (ERROR/WARN/INFO/DEBUG/TRACE, case-insensitive) as sent over the wire
+fn parse_level(level: &str) -> Result<Level> {
+    level
+        .parse()
+        .map_err(|_| anyhow!("invalid log level: {level}"))
+}
+
+/// a read-back query over stored logs, returned as a `Vec<serde_json::Value>` Response;
+/// `tail` takes precedence over `offset`/`limit` when both are given
+#[derive(Debug, serde::Deserialize, serde::Serialize, process_macros::SerdeJsonInto)]
+enum QueryRequest {
+    Query {
+        package_id: Option<PackageId>,
+        node: Option<String>,
+        level: Option<String>,
+        contains: Option<String>,
+        since: Option<u64>,
+        until: Option<u64>,
+        tail: Option<usize>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    },
+}
+
+/// drive_path          : populated at process init()
+/// log_files           : added to over the run of the program to reduce number of VFS calls
+/// allowed_packages    : packages to log for; empty -> all
+/// whitelist           : nodes to log for; empty -> all
+/// blacklist           : nodes to NOT log for; empty -> all
+/// min_level           : global minimum level to log; entries less severe are dropped
+/// package_min_levels  : per-package override of `min_level`
+/// log_file_sizes      : running byte count of each open `.log` file, to avoid re-stat'ing on every append
+/// max_log_bytes       : size threshold at which a `.log` file is rotated
+/// max_log_files       : number of rotated, gzip-compressed segments to retain per `Address`
+/// peer_versions       : last negotiated `LoggingRequest::Log` protocol version per `Address`
+/// metrics             : counters over accepted/dropped logs
 struct State {
     drive_path: String,
     log_files: Files,
     allowed_packages: Packages,
     whitelist: Nodes,
     blacklist: Nodes,
+    min_level: Level,
+    package_min_levels: HashMap<PackageId, Level>,
+    log_file_sizes: HashMap<Address, u64>,
+    max_log_bytes: u64,
+    max_log_files: usize,
+    peer_versions: HashMap<Address, u32>,
+    metrics: Metrics,
 }
 
 impl State {
@@ -56,25 +160,48 @@ impl State {
             allowed_packages: HashSet::new(),
             whitelist: HashSet::new(),
             blacklist: HashSet::new(),
+            min_level: Level::TRACE,
+            package_min_levels: HashMap::new(),
+            log_file_sizes: HashMap::new(),
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            max_log_files: DEFAULT_MAX_LOG_FILES,
+            peer_versions: HashMap::new(),
+            metrics: Metrics::default(),
         }
     }
 }
 
+/// why a log entry was dropped, for `Metrics::dropped_by_reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum DropReason {
+    UnwhitelistedNode,
+    BlacklistedNode,
+    DisallowedPackage,
+    BelowLevel,
+}
+
 /// check if node is on whitelist (if it exists) & not on blacklist (if it exists)
 ///
 /// return value of None -> node is allowed;
-/// return value of Some -> node is not allowed (and an explanatory message)
-fn is_node_allowed(source: &Address, state: &State) -> Option<String> {
+/// return value of Some -> node is not allowed (and the reason & an explanatory message)
+fn is_node_allowed(source: &Address, state: &State) -> Option<(DropReason, String)> {
     if !state.whitelist.is_empty() && !state.whitelist.contains(source.node()) {
-        return Some(format!(
-            "dropping log Request from un-whitelisted node {}",
-            source.node(),
+        return Some((
+            DropReason::UnwhitelistedNode,
+            format!(
+                "dropping log Request from un-whitelisted node {}",
+                source.node(),
+            ),
         ));
     }
     if !state.blacklist.is_empty() && state.blacklist.contains(source.node()) {
-        return Some(format!(
-            "dropping log Request from blacklisted node {}",
-            source.node(),
+        return Some((
+            DropReason::BlacklistedNode,
+            format!(
+                "dropping log Request from blacklisted node {}",
+                source.node(),
+            ),
         ));
     }
     None
@@ -83,20 +210,64 @@ fn is_node_allowed(source: &Address, state: &State) -> Option<String> {
 /// check if node is on whitelist (if it exists) & not on blacklist (if it exists)
 ///
 /// return value of None -> node is allowed;
-/// return value of Some -> node is not allowed (and an explanatory message)
-fn is_package_allowed(source: &Address, state: &State) -> Option<String> {
+/// return value of Some -> package is not allowed (and the reason & an explanatory message)
+fn is_package_allowed(source: &Address, state: &State) -> Option<(DropReason, String)> {
     if !state.allowed_packages.is_empty() && !state.allowed_packages.contains(&source.package_id())
     {
-        Some(format!(
-            "dropping log Request from package {}; not amongst allowed packages: {:?}",
-            source.package_id(),
-            state.allowed_packages,
+        Some((
+            DropReason::DisallowedPackage,
+            format!(
+                "dropping log Request from package {}; not amongst allowed packages: {:?}",
+                source.package_id(),
+                state.allowed_packages,
+            ),
         ))
     } else {
         None
     }
 }
 
+/// rotate `{process}.log` for `source`: shift existing `.log.N.gz` segments up by one
+/// (dropping anything beyond `state.max_log_files`), gzip the current `.log` into `.log.1.gz`,
+/// and re-open a fresh, empty `.log` file in its place
+fn rotate_log_file(source: &Address, state: &mut State) -> Result<()> {
+    let log_dir_path = format!("{}/{}", state.drive_path, source.package_id());
+    let base_path = format!("{log_dir_path}/{}.log", source.process());
+
+    // drop the cached handle so the underlying VFS entry is free to be replaced
+    state.log_files.remove(source);
+    state.log_file_sizes.remove(source);
+
+    for i in (1..state.max_log_files).rev() {
+        let from = format!("{base_path}.{i}.gz");
+        let Ok(bytes) = open_file(&from, false, None).and_then(|f| f.read()) else {
+            continue;
+        };
+        let _ = remove_file(&from, None);
+        if i + 1 <= state.max_log_files {
+            let to = format!("{base_path}.{}.gz", i + 1);
+            create_file(&to, None)?.write(&bytes)?;
+        }
+    }
+
+    if state.max_log_files > 0 {
+        let raw = open_file(&base_path, false, None)?.read()?;
+        let mut gz_bytes = Vec::new();
+        {
+            let mut encoder = GzEncoder::new(&mut gz_bytes, Compression::default());
+            encoder.write_all(&raw)?;
+            encoder.finish()?;
+        }
+        create_file(&format!("{base_path}.1.gz"), None)?.write(&gz_bytes)?;
+    }
+    remove_file(&base_path, None)?;
+
+    let fresh_file = open_file(&base_path, true, None)?;
+    state.log_files.insert(source.clone(), fresh_file);
+    state.log_file_sizes.insert(source.clone(), 0);
+    Ok(())
+}
+
 fn handle_logging_request(
     source: &Address,
     request: &LoggingRequest,
@@ -104,21 +275,302 @@ fn handle_logging_request(
 ) -> Result<()> {
     match request {
         LoggingRequest::Log(ref log) => {
+            match state.peer_versions.get(source) {
+                None => info!(
+                    "accepting Log from {source} with no prior Hello; assuming protocol v{MIN_SUPPORTED_LOG_PROTOCOL_VERSION}"
+                ),
+                Some(&version)
+                    if !(MIN_SUPPORTED_LOG_PROTOCOL_VERSION..=MAX_SUPPORTED_LOG_PROTOCOL_VERSION)
+                        .contains(&version) =>
+                {
+                    return Err(anyhow!(
+                        "rejecting Log from {source}: unsupported protocol version {version}"
+                    ));
+                }
+                Some(_) => {}
+            }
             let mut log: serde_json::Value = serde_json::from_slice(log)?;
+            let level = log
+                .get("level")
+                .and_then(|v| v.as_str())
+                .and_then(|s| parse_level(s).ok());
+            if let Some(level) = level {
+                let threshold = state
+                    .package_min_levels
+                    .get(&source.package_id())
+                    .copied()
+                    .unwrap_or(state.min_level);
+                if level > threshold {
+                    state.metrics.record_drop(DropReason::BelowLevel);
+                    return Ok(());
+                }
+            }
             log["source"] = serde_json::json!(source);
             let log = serde_json::to_vec(&log).unwrap();
-            let log_file = state.log_files.entry(source.clone()).or_insert_with(|| {
-                let log_dir_path = format!("{}/{}", state.drive_path, source.package_id());
-                let _log_dir = open_dir(&log_dir_path, true, None).expect("failed to open log dir");
-                let log_file_path = format!("{log_dir_path}/{}.log", source.process());
-                open_file(&log_file_path, true, None).expect("failed to open log file")
-            });
-            log_file.append(&log)?;
+            // terminate each entry with a newline so the stored file is NDJSON
+            let log = [log.as_slice(), b"\n"].concat();
+            let log_len = log.len() as u64;
+            let log_dir_path = format!("{}/{}", state.drive_path, source.package_id());
+            let log_file_path = format!("{log_dir_path}/{}.log", source.process());
+            let is_freshly_opened = !state.log_files.contains_key(source);
+            {
+                let log_file = state.log_files.entry(source.clone()).or_insert_with(|| {
+                    let _log_dir =
+                        open_dir(&log_dir_path, true, None).expect("failed to open log dir");
+                    open_file(&log_file_path, true, None).expect("failed to open log file")
+                });
+                log_file.append(&log)?;
+            }
+            state.metrics.record_accepted(source);
+            // a freshly opened handle may have pre-existing bytes from before this process
+            // started (or restarted); re-stat it once so rotation sees the file's true size
+            // instead of assuming it started empty
+            let size = if is_freshly_opened {
+                open_file(&log_file_path, false, None)
+                    .and_then(|f| f.read())
+                    .map(|bytes| bytes.len() as u64)
+                    .unwrap_or(log_len)
+            } else {
+                state.log_file_sizes.get(source).copied().unwrap_or(0) + log_len
+            };
+            state.log_file_sizes.insert(source.clone(), size);
+            if size > state.max_log_bytes {
+                rotate_log_file(source, state)?;
+            }
         }
     }
     Ok(())
 }
 
+/// split a stored log file on newlines into its individual NDJSON entries
+fn parse_ndjson_lines(bytes: &[u8]) -> Vec<serde_json::Value> {
+    bytes
+        .split(|&b| b == b'\n')
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| serde_json::from_slice(line).ok())
+        .collect()
+}
+
+/// apply a `QueryRequest::Query`'s filters to a single stored log entry
+fn entry_matches(
+    entry: &serde_json::Value,
+    node: Option<&str>,
+    level: Option<Level>,
+    contains: Option<&str>,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> bool {
+    if let Some(node) = node {
+        let entry_node = entry
+            .get("source")
+            .and_then(|s| s.get("node"))
+            .and_then(|n| n.as_str());
+        if entry_node != Some(node) {
+            return false;
+        }
+    }
+    if let Some(level) = level {
+        let entry_level = entry
+            .get("level")
+            .and_then(|v| v.as_str())
+            .and_then(|s| parse_level(s).ok());
+        if entry_level != Some(level) {
+            return false;
+        }
+    }
+    if let Some(contains) = contains {
+        let message = entry.get("message").and_then(|m| m.as_str()).unwrap_or("");
+        if !message.contains(contains) {
+            return false;
+        }
+    }
+    if let Some(since) = since {
+        if entry.get("timestamp").and_then(|t| t.as_u64()).unwrap_or(0) < since {
+            return false;
+        }
+    }
+    if let Some(until) = until {
+        if entry.get("timestamp").and_then(|t| t.as_u64()).unwrap_or(0) > until {
+            return false;
+        }
+    }
+    true
+}
+
+/// list the `.log` files directly under a package's directory on the `remote_log` drive
+fn list_log_file_paths_in(package_dir_path: &str) -> Vec<String> {
+    let Ok(entries) = open_dir(package_dir_path, false, None).and_then(|d| d.read()) else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter(|entry| entry.file_type == FileType::File && entry.path.ends_with(".log"))
+        .map(|entry| entry.path)
+        .collect()
+}
+
+/// find the `.log` files to read for a query: reads straight from the `remote_log` drive (not
+/// `state.log_files`, which only holds the handles this process happens to have opened since its
+/// last restart) so a query sees everything on disk, including logs written before this run
+fn list_log_file_paths(drive_path: &str, package_id: Option<&PackageId>) -> Vec<String> {
+    if let Some(package_id) = package_id {
+        return list_log_file_paths_in(&format!("{drive_path}/{package_id}"));
+    }
+    let Ok(package_dirs) = open_dir(drive_path, false, None).and_then(|d| d.read()) else {
+        return Vec::new();
+    };
+    package_dirs
+        .into_iter()
+        .filter(|entry| entry.file_type == FileType::Directory)
+        .flat_map(|entry| list_log_file_paths_in(&entry.path))
+        .collect()
+}
+
+/// read the stored `.log` files that match `package_id` (or all of them, if unset), apply the
+/// query's filters, and page the results via `tail`, or `offset`/`limit` if `tail` is unset
+fn handle_query_request(query: QueryRequest, state: &State) -> Result<Vec<serde_json::Value>> {
+    let QueryRequest::Query {
+        package_id,
+        node,
+        level,
+        contains,
+        since,
+        until,
+        tail,
+        offset,
+        limit,
+    } = query;
+    let level = level.map(|l| parse_level(&l)).transpose()?;
+
+    let mut entries = Vec::new();
+    for log_file_path in list_log_file_paths(&state.drive_path, package_id.as_ref()) {
+        let Ok(bytes) = open_file(&log_file_path, false, None).and_then(|f| f.read()) else {
+            continue;
+        };
+        let matched = parse_ndjson_lines(&bytes).into_iter().filter(|entry| {
+            entry_matches(
+                entry,
+                node.as_deref(),
+                level,
+                contains.as_deref(),
+                since,
+                until,
+            )
+        });
+        match tail {
+            // a `tail(n)` query only ever needs this file's `n` most recent matches once every
+            // file is merged below, so keep a bounded window instead of buffering all of them
+            Some(n) => {
+                let mut window = VecDeque::with_capacity(n);
+                for entry in matched {
+                    if window.len() == n {
+                        window.pop_front();
+                    }
+                    window.push_back(entry);
+                }
+                entries.extend(window);
+            }
+            None => entries.extend(matched),
+        }
+    }
+
+    // entries arrive file-by-file in directory order, not global time order; sort so
+    // `tail`/`offset`/`limit` mean something once more than one file is involved
+    entries.sort_by_key(|entry| entry.get("timestamp").and_then(|t| t.as_u64()).unwrap_or(0));
+
+    if let Some(n) = tail {
+        let len = entries.len();
+        entries = entries.split_off(len.saturating_sub(n));
+    } else {
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(entries.len());
+        entries = entries.into_iter().skip(offset).take(limit).collect();
+    }
+
+    Ok(entries)
+}
+
+/// name of the file on the `remote_log` drive that all `InternalRequest`-configurable policy is
+/// persisted to, so it survives a process restart
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// `Level` isn't `Serialize`/`Deserialize`, and `PackageId` can't be a `serde_json` map key, so
+/// levels round-trip through their `Display`/`FromStr` strings, same as over the wire
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct PersistedConfig {
+    allowed_packages: Packages,
+    whitelist: Nodes,
+    blacklist: Nodes,
+    min_level: String,
+    package_min_levels: HashMap<String, String>,
+    max_log_bytes: u64,
+    max_log_files: usize,
+}
+
+impl Default for PersistedConfig {
+    fn default() -> Self {
+        Self {
+            allowed_packages: Packages::new(),
+            whitelist: Nodes::new(),
+            blacklist: Nodes::new(),
+            min_level: Level::TRACE.to_string(),
+            package_min_levels: HashMap::new(),
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            max_log_files: DEFAULT_MAX_LOG_FILES,
+        }
+    }
+}
+
+fn config_path(drive_path: &str) -> String {
+    format!("{drive_path}/{CONFIG_FILE_NAME}")
+}
+
+/// write all of `state`'s `InternalRequest`-configurable policy to `config.json` on the drive
+fn save_config(state: &State) -> Result<()> {
+    let config = PersistedConfig {
+        allowed_packages: state.allowed_packages.clone(),
+        whitelist: state.whitelist.clone(),
+        blacklist: state.blacklist.clone(),
+        min_level: state.min_level.to_string(),
+        package_min_levels: state
+            .package_min_levels
+            .iter()
+            .map(|(package_id, level)| (package_id.to_string(), level.to_string()))
+            .collect(),
+        max_log_bytes: state.max_log_bytes,
+        max_log_files: state.max_log_files,
+    };
+    create_file(&config_path(&state.drive_path), None)?.write(&serde_json::to_vec(&config)?)?;
+    Ok(())
+}
+
+/// read `config.json` back at `init`; a missing file just means a fresh, default config
+fn load_config(drive_path: &str) -> PersistedConfig {
+    open_file(&config_path(drive_path), false, None)
+        .and_then(|f| f.read())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// record the peer's declared protocol version (so later `Log`s from it can be checked against
+/// it) and tell it what this build supports
+fn handle_version_request(
+    source: &Address,
+    request: VersionRequest,
+    state: &mut State,
+) -> VersionResponse {
+    let VersionRequest::Hello(version) = request;
+    let accepted = (MIN_SUPPORTED_LOG_PROTOCOL_VERSION..=MAX_SUPPORTED_LOG_PROTOCOL_VERSION)
+        .contains(&version);
+    state.peer_versions.insert(source.clone(), version);
+    VersionResponse {
+        min_supported: MIN_SUPPORTED_LOG_PROTOCOL_VERSION,
+        max_supported: MAX_SUPPORTED_LOG_PROTOCOL_VERSION,
+        accepted,
+    }
+}
+
 fn handle_internal_request(
     our: &Address,
     source: &Address,
@@ -137,7 +589,26 @@ fn handle_internal_request(
         InternalRequest::UnwhitelistNode(ref node) => state.whitelist.remove(node),
         InternalRequest::BlacklistNode(node) => state.blacklist.insert(node),
         InternalRequest::UnblacklistNode(ref node) => state.blacklist.remove(node),
+        InternalRequest::SetMinLevel(level) => {
+            state.min_level = parse_level(&level)?;
+            true
+        }
+        InternalRequest::SetPackageMinLevel(package_id, level) => {
+            state
+                .package_min_levels
+                .insert(package_id, parse_level(&level)?);
+            true
+        }
+        InternalRequest::SetMaxLogBytes(max_bytes) => {
+            state.max_log_bytes = max_bytes;
+            true
+        }
+        InternalRequest::SetMaxLogFileCount(max_files) => {
+            state.max_log_files = max_files;
+            true
+        }
     };
+    save_config(state)?;
     Ok(())
 }
 
@@ -146,18 +617,39 @@ fn handle_message(our: &Address, message: &Message, state: &mut State) -> Result
         return Err(anyhow!("unexpected Response: {:?}", message));
     }
     let source = message.source();
-    if let Some(ref failure_message) = is_node_allowed(source, state) {
-        info!("{failure_message}");
-        return Ok(());
-    }
-    if let Some(ref failure_message) = is_package_allowed(source, state) {
-        info!("{failure_message}");
-        return Ok(());
-    }
 
     match message.body().try_into()? {
-        Req::LoggingRequest(ref request) => handle_logging_request(source, request, state)?,
+        // the node/package allow-list governs log *ingestion* only; introspection requests
+        // (Query/Version/Metrics) are admin APIs and must never be silently swallowed by it
+        Req::LoggingRequest(ref request) => {
+            if let Some((reason, failure_message)) = is_node_allowed(source, state) {
+                info!("{failure_message}");
+                state.metrics.record_drop(reason);
+                return Ok(());
+            }
+            if let Some((reason, failure_message)) = is_package_allowed(source, state) {
+                info!("{failure_message}");
+                state.metrics.record_drop(reason);
+                return Ok(());
+            }
+            handle_logging_request(source, request, state)?
+        }
         Req::InternalRequest(request) => handle_internal_request(our, source, request, state)?,
+        Req::QueryRequest(request) => {
+            let entries = handle_query_request(request, state)?;
+            Response::new().body(serde_json::to_vec(&entries)?).send()?;
+        }
+        Req::VersionRequest(request) => {
+            let response = handle_version_request(source, request, state);
+            Response::new()
+                .body(serde_json::to_vec(&response)?)
+                .send()?;
+        }
+        Req::MetricsRequest(MetricsRequest::GetMetrics) => {
+            Response::new()
+                .body(serde_json::to_vec(&state.metrics)?)
+                .send()?;
+        }
     }
     Ok(())
 }
@@ -169,6 +661,32 @@ fn init(our: Address) {
     let drive_path = create_drive(our.package_id(), "remote_log", None).unwrap();
 
     let mut state = State::new(drive_path);
+    let PersistedConfig {
+        allowed_packages,
+        whitelist,
+        blacklist,
+        min_level,
+        package_min_levels,
+        max_log_bytes,
+        max_log_files,
+    } = load_config(&state.drive_path);
+    state.allowed_packages = allowed_packages;
+    state.whitelist = whitelist;
+    state.blacklist = blacklist;
+    if let Ok(level) = parse_level(&min_level) {
+        state.min_level = level;
+    }
+    state.package_min_levels = package_min_levels
+        .into_iter()
+        .filter_map(|(package_id, level)| {
+            Some((
+                package_id.parse::<PackageId>().ok()?,
+                parse_level(&level).ok()?,
+            ))
+        })
+        .collect();
+    state.max_log_bytes = max_log_bytes;
+    state.max_log_files = max_log_files;
 
     loop {
         match await_message() {